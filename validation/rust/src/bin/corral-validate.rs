@@ -0,0 +1,277 @@
+//! Standalone CLI wrapping `corral_validate` for scripting and operator
+//! use, without embedding the library: inspect a token, list a user's
+//! sessions, or run the managed Node auth server under a supervisor.
+
+use clap::{Parser, Subcommand, ValueEnum};
+use corral_validate::CorralValidator;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "corral-validate", version, about = "Inspect Corral/Better Auth sessions and manage the auth server")]
+struct Cli {
+    /// Path to the Better Auth SQLite database, or a postgres:// / mysql:// URL.
+    #[arg(long, global = true, default_value = "auth.db")]
+    db: String,
+
+    /// Verify JWTs signed with this HMAC secret (see also --jwks-url).
+    /// Without either flag, `validate` only checks opaque session tokens.
+    #[cfg(feature = "jwt")]
+    #[arg(long, global = true)]
+    jwt_secret: Option<String>,
+
+    /// Verify JWTs using keys fetched (and cached) from this JWKS endpoint.
+    #[cfg(feature = "jwt")]
+    #[arg(long, global = true)]
+    jwks_url: Option<String>,
+
+    /// Algorithm --jwt-secret/--jwks-url's key(s) may verify with (default:
+    /// hs256 for --jwt-secret, rs256 for --jwks-url). Never derived from the
+    /// token itself, since that's attacker-controlled.
+    #[cfg(feature = "jwt")]
+    #[arg(long, global = true)]
+    jwt_algorithm: Option<JwtAlgorithm>,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+/// CLI-friendly mirror of the `jsonwebtoken::Algorithm` variants an operator
+/// is likely to configure.
+#[cfg(feature = "jwt")]
+#[derive(Clone, Copy, ValueEnum)]
+#[value(rename_all = "lower")]
+enum JwtAlgorithm {
+    Hs256,
+    Hs384,
+    Hs512,
+    Rs256,
+    Rs384,
+    Rs512,
+    Es256,
+    Es384,
+    Ps256,
+    Ps384,
+    Ps512,
+    EdDsa,
+}
+
+#[cfg(feature = "jwt")]
+impl From<JwtAlgorithm> for jsonwebtoken::Algorithm {
+    fn from(alg: JwtAlgorithm) -> Self {
+        match alg {
+            JwtAlgorithm::Hs256 => jsonwebtoken::Algorithm::HS256,
+            JwtAlgorithm::Hs384 => jsonwebtoken::Algorithm::HS384,
+            JwtAlgorithm::Hs512 => jsonwebtoken::Algorithm::HS512,
+            JwtAlgorithm::Rs256 => jsonwebtoken::Algorithm::RS256,
+            JwtAlgorithm::Rs384 => jsonwebtoken::Algorithm::RS384,
+            JwtAlgorithm::Rs512 => jsonwebtoken::Algorithm::RS512,
+            JwtAlgorithm::Es256 => jsonwebtoken::Algorithm::ES256,
+            JwtAlgorithm::Es384 => jsonwebtoken::Algorithm::ES384,
+            JwtAlgorithm::Ps256 => jsonwebtoken::Algorithm::PS256,
+            JwtAlgorithm::Ps384 => jsonwebtoken::Algorithm::PS384,
+            JwtAlgorithm::Ps512 => jsonwebtoken::Algorithm::PS512,
+            JwtAlgorithm::EdDsa => jsonwebtoken::Algorithm::EdDSA,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Resolve a session token (or, with --jwt-secret/--jwks-url, a JWT)
+    /// and print the user as JSON.
+    Validate {
+        /// Session token or JWT to validate.
+        token: String,
+    },
+    /// List a user's active sessions as JSON.
+    Sessions {
+        /// User id to list sessions for.
+        user_id: String,
+    },
+    /// Run the managed Node auth server in the foreground.
+    Serve,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match &cli.command {
+        Commands::Validate { token } => validate(&cli, token),
+        Commands::Sessions { user_id } => sessions(&cli, user_id),
+        Commands::Serve => serve(&cli.db),
+    }
+}
+
+/// Print `corral-validate: {err}` to stderr and return the failure exit code,
+/// used by every subcommand below to report its errors.
+fn report<E: std::fmt::Display>(err: E) -> ExitCode {
+    eprintln!("corral-validate: {err}");
+    ExitCode::FAILURE
+}
+
+fn open(cli: &Cli) -> Result<CorralValidator, ExitCode> {
+    let builder = CorralValidator::builder(&cli.db);
+    #[cfg(feature = "jwt")]
+    let builder = match (&cli.jwks_url, &cli.jwt_secret) {
+        (Some(url), _) => {
+            let algorithm = cli.jwt_algorithm.map(Into::into).unwrap_or(jsonwebtoken::Algorithm::RS256);
+            builder.with_jwks_url(url.clone(), [algorithm])
+        }
+        (None, Some(secret)) => {
+            let algorithm = cli.jwt_algorithm.map(Into::into).unwrap_or(jsonwebtoken::Algorithm::HS256);
+            builder.with_jwt_key(jsonwebtoken::DecodingKey::from_secret(secret.as_bytes()), algorithm)
+        }
+        (None, None) => builder,
+    };
+    builder.build().map_err(report)
+}
+
+fn validate(cli: &Cli, token: &str) -> ExitCode {
+    let validator = match open(cli) {
+        Ok(v) => v,
+        Err(code) => return code,
+    };
+    #[cfg(feature = "jwt")]
+    let result = validator.validate_token(token);
+    #[cfg(not(feature = "jwt"))]
+    let result = validator.validate_session(token);
+    match result {
+        Ok(user) => {
+            println!("{}", serde_json::to_string_pretty(&user).expect("User always serializes"));
+            ExitCode::SUCCESS
+        }
+        Err(e) => report(e),
+    }
+}
+
+fn sessions(cli: &Cli, user_id: &str) -> ExitCode {
+    let validator = match open(cli) {
+        Ok(v) => v,
+        Err(code) => return code,
+    };
+    match validator.list_sessions(user_id) {
+        Ok(sessions) => {
+            println!("{}", serde_json::to_string_pretty(&sessions).expect("SessionInfo always serializes"));
+            ExitCode::SUCCESS
+        }
+        Err(e) => report(e),
+    }
+}
+
+fn serve(db: &str) -> ExitCode {
+    let validator = match CorralValidator::builder(db).with_auth_server(true).build() {
+        Ok(v) => v,
+        Err(e) => return report(e),
+    };
+    // Held for the process lifetime so the auth-server child stays alive.
+    // SIGINT/SIGTERM terminate the process without running destructors, so
+    // `Drop` alone can't be relied on to stop the child under a supervisor;
+    // install a handler that stops it explicitly before exiting. Requires
+    // ctrlc's "termination" feature to also catch SIGTERM, not just SIGINT.
+    let validator = std::sync::Arc::new(validator);
+    let handler_validator = validator.clone();
+    if let Err(e) = ctrlc::set_handler(move || {
+        handler_validator.stop_auth_server();
+        std::process::exit(0);
+    }) {
+        return report(e);
+    }
+    println!("corral-validate: auth server running, Ctrl-C to stop");
+    loop {
+        std::thread::sleep(std::time::Duration::from_secs(3600));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db_path(name: &str) -> String {
+        let mut path = std::env::temp_dir();
+        path.push(format!("corral-validate-cli-test-{name}-{}.db", std::process::id()));
+        path.to_string_lossy().into_owned()
+    }
+
+    fn setup_db(path: &str) {
+        let conn = rusqlite::Connection::open(path).unwrap();
+        conn.execute_batch(
+            r#"
+            CREATE TABLE "user" (
+                "id" TEXT PRIMARY KEY,
+                "email" TEXT NOT NULL,
+                "name" TEXT,
+                "plan" TEXT,
+                "role" TEXT,
+                "emailVerified" INTEGER NOT NULL,
+                "createdAt" TEXT NOT NULL,
+                "banned" INTEGER
+            );
+            CREATE TABLE "session" (
+                "token" TEXT PRIMARY KEY,
+                "userId" TEXT NOT NULL,
+                "expiresAt" TEXT NOT NULL,
+                "createdAt" TEXT NOT NULL,
+                "ipAddress" TEXT,
+                "userAgent" TEXT
+            );
+            INSERT INTO "user" VALUES ('user_1', 'a@example.com', NULL, 'free', 'user', 1, '2024-01-01T00:00:00Z', 0);
+            INSERT INTO "session" VALUES ('tok_abc', 'user_1', '2099-01-01T00:00:00Z', '2024-01-01T00:00:00Z', NULL, NULL);
+            "#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn cli_parses_db_and_subcommand() {
+        let cli = Cli::try_parse_from(["corral-validate", "--db", "test.db", "validate", "tok_abc"]).unwrap();
+        assert_eq!(cli.db, "test.db");
+        assert!(matches!(cli.command, Commands::Validate { ref token } if token == "tok_abc"));
+    }
+
+    #[test]
+    fn validate_prints_user_for_known_session() {
+        let path = temp_db_path("validate");
+        setup_db(&path);
+        let cli = Cli::try_parse_from(["corral-validate", "--db", &path, "validate", "tok_abc"]).unwrap();
+
+        assert_eq!(validate(&cli, "tok_abc"), ExitCode::SUCCESS);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn validate_fails_for_unknown_session() {
+        let path = temp_db_path("validate-miss");
+        setup_db(&path);
+        let cli = Cli::try_parse_from(["corral-validate", "--db", &path, "validate", "no-such-token"]).unwrap();
+
+        assert_eq!(validate(&cli, "no-such-token"), ExitCode::FAILURE);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn sessions_lists_a_users_sessions() {
+        let path = temp_db_path("sessions");
+        setup_db(&path);
+        let cli = Cli::try_parse_from(["corral-validate", "--db", &path, "sessions", "user_1"]).unwrap();
+
+        assert_eq!(sessions(&cli, "user_1"), ExitCode::SUCCESS);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[cfg(feature = "jwt")]
+    #[test]
+    fn open_builds_validator_with_default_algorithm_for_jwt_secret() {
+        let path = temp_db_path("jwt-secret");
+        setup_db(&path);
+        let cli = Cli::try_parse_from([
+            "corral-validate", "--db", &path, "--jwt-secret", "shh", "validate", "tok_abc",
+        ])
+        .unwrap();
+
+        assert!(open(&cli).is_ok());
+
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,166 @@
+//! Typed error type for the public API.
+//!
+//! `validate_session` and friends used to return a bare `rusqlite::Result`,
+//! which left "token not found", "session expired" and "database
+//! unreachable" all looking the same to a caller. `CorralError` gives each
+//! of those a distinct variant, and (behind the `axum` feature) maps them
+//! to HTTP status codes so a rejected `CorralUser` extraction carries an
+//! actionable response instead of a bare `500`.
+
+use crate::StoreError;
+use std::fmt;
+
+/// Failure modes surfaced by `CorralValidator`'s public methods.
+#[derive(Debug)]
+pub enum CorralError {
+    /// No session or user matched (unknown or malformed token, unknown
+    /// user id, or a disabled account).
+    NotFound,
+    /// The session exists but its `expiresAt` has passed.
+    Expired,
+    /// The underlying store failed — a pool error, a query error, or (for
+    /// JWTs) a signature/claims verification failure. Holds the real
+    /// `StoreError` from whichever backend raised it, so a Postgres pool
+    /// timeout and a malformed row stay distinguishable in `source()`
+    /// instead of collapsing into one opaque variant.
+    Database(StoreError),
+    /// A stored `expiresAt` couldn't be parsed as RFC3339 or
+    /// `YYYY-MM-DD HH:MM:SS`.
+    MalformedExpiry,
+    /// `with_auth_server(true)` was set but `server/auth.js` couldn't be
+    /// found or spawned.
+    AuthServerUnavailable,
+}
+
+impl fmt::Display for CorralError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CorralError::NotFound => write!(f, "session or user not found"),
+            CorralError::Expired => write!(f, "session has expired"),
+            CorralError::Database(e) => write!(f, "database error: {e}"),
+            CorralError::MalformedExpiry => write!(f, "stored expiry timestamp is malformed"),
+            CorralError::AuthServerUnavailable => write!(f, "auth server is not available"),
+        }
+    }
+}
+
+impl std::error::Error for CorralError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CorralError::Database(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<StoreError> for CorralError {
+    fn from(e: StoreError) -> Self {
+        CorralError::Database(e)
+    }
+}
+
+#[cfg(feature = "axum")]
+#[derive(serde::Serialize)]
+struct ErrorBody {
+    status: u16,
+    message: String,
+}
+
+#[cfg(feature = "axum")]
+impl axum::response::IntoResponse for CorralError {
+    fn into_response(self) -> axum::response::Response {
+        let status = match &self {
+            CorralError::NotFound => axum::http::StatusCode::NOT_FOUND,
+            CorralError::Expired => axum::http::StatusCode::UNAUTHORIZED,
+            CorralError::Database(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            CorralError::MalformedExpiry => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            CorralError::AuthServerUnavailable => axum::http::StatusCode::SERVICE_UNAVAILABLE,
+        };
+        let body = ErrorBody { status: status.as_u16(), message: self.to_string() };
+        (status, axum::Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_messages_are_distinct_per_variant() {
+        assert_eq!(CorralError::NotFound.to_string(), "session or user not found");
+        assert_eq!(CorralError::Expired.to_string(), "session has expired");
+        assert_eq!(
+            CorralError::MalformedExpiry.to_string(),
+            "stored expiry timestamp is malformed"
+        );
+        assert_eq!(
+            CorralError::AuthServerUnavailable.to_string(),
+            "auth server is not available"
+        );
+    }
+
+    #[cfg(feature = "axum")]
+    #[test]
+    fn into_response_maps_each_variant_to_its_status_code() {
+        use axum::response::IntoResponse;
+
+        let cases: Vec<(CorralError, axum::http::StatusCode)> = vec![
+            (CorralError::NotFound, axum::http::StatusCode::NOT_FOUND),
+            (CorralError::Expired, axum::http::StatusCode::UNAUTHORIZED),
+            (CorralError::Database("boom".into()), axum::http::StatusCode::INTERNAL_SERVER_ERROR),
+            (CorralError::MalformedExpiry, axum::http::StatusCode::INTERNAL_SERVER_ERROR),
+            (CorralError::AuthServerUnavailable, axum::http::StatusCode::SERVICE_UNAVAILABLE),
+        ];
+        for (err, expected) in cases {
+            assert_eq!(err.into_response().status(), expected);
+        }
+    }
+
+    // Regression test for a lookup table like the one above: exercises the
+    // disabled-account path through the public `CorralValidator::validate_session`
+    // API (not just `SqliteStore` directly) and confirms it still maps to 404.
+    #[cfg(feature = "axum")]
+    #[test]
+    fn disabled_account_rejects_with_not_found_end_to_end() {
+        use axum::response::IntoResponse;
+
+        let mut path = std::env::temp_dir();
+        path.push(format!("corral-validate-error-test-disabled-{}.db", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute_batch(
+            r#"
+            CREATE TABLE "user" (
+                "id" TEXT PRIMARY KEY,
+                "email" TEXT NOT NULL,
+                "name" TEXT,
+                "plan" TEXT,
+                "role" TEXT,
+                "emailVerified" INTEGER NOT NULL,
+                "createdAt" TEXT NOT NULL,
+                "banned" INTEGER
+            );
+            CREATE TABLE "session" (
+                "token" TEXT PRIMARY KEY,
+                "userId" TEXT NOT NULL,
+                "expiresAt" TEXT NOT NULL,
+                "createdAt" TEXT NOT NULL,
+                "ipAddress" TEXT,
+                "userAgent" TEXT
+            );
+            INSERT INTO "user" VALUES ('user_1', 'a@example.com', NULL, 'free', 'user', 1, '2024-01-01T00:00:00Z', 1);
+            INSERT INTO "session" VALUES ('tok_abc', 'user_1', '2099-01-01T00:00:00Z', '2024-01-01T00:00:00Z', NULL, NULL);
+            "#,
+        )
+        .unwrap();
+        drop(conn);
+
+        let validator = crate::CorralValidator::builder(&path).build().unwrap();
+        let err = validator.validate_session("tok_abc").unwrap_err();
+        assert!(matches!(err, CorralError::NotFound));
+        assert_eq!(err.into_response().status(), axum::http::StatusCode::NOT_FOUND);
+
+        std::fs::remove_file(&path).ok();
+    }
+}
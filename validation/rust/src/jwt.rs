@@ -0,0 +1,228 @@
+//! Stateless JWT session validation, available behind the `jwt` feature.
+//!
+//! Better Auth's JWT plugin mints short-lived signed tokens carrying the
+//! user id, plan and role as custom claims. When a `CorralValidator` is
+//! configured with a verification key (or a JWKS URL), `validate_token`
+//! verifies the token locally and builds a `User` without touching the
+//! database, falling back to the opaque-session DB lookup for tokens that
+//! aren't JWTs.
+
+use crate::User;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long fetched JWKS keys are cached before being re-fetched.
+const JWKS_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+#[derive(Deserialize)]
+struct Claims {
+    sub: String,
+    email: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default = "default_plan")]
+    plan: String,
+    #[serde(default = "default_role")]
+    role: String,
+    #[serde(default)]
+    email_verified: bool,
+    #[serde(default)]
+    iat: Option<i64>,
+}
+
+fn default_plan() -> String {
+    "free".into()
+}
+
+fn default_role() -> String {
+    "user".into()
+}
+
+/// Where `CorralValidator` gets the key(s) used to verify JWTs. Each source
+/// also pins the algorithm(s) its key(s) may be used with, so verification
+/// never trusts the signing algorithm an attacker-controlled JWT header
+/// claims — see `validate_jwt`.
+pub(crate) enum JwtKeySource {
+    /// A single fixed verification key.
+    Static(DecodingKey, Vec<Algorithm>),
+    /// Keys fetched from a JWKS endpoint and cached by `kid`.
+    Jwks {
+        url: String,
+        algorithms: Vec<Algorithm>,
+        cache: Mutex<Option<(HashMap<String, DecodingKey>, Instant)>>,
+    },
+}
+
+impl JwtKeySource {
+    fn resolve(&self, kid: Option<&str>) -> Result<(DecodingKey, Vec<Algorithm>), String> {
+        match self {
+            JwtKeySource::Static(key, algorithms) => Ok((key.clone(), algorithms.clone())),
+            JwtKeySource::Jwks { url, algorithms, cache } => {
+                let kid = kid.ok_or("JWT has no `kid` header but a JWKS URL is configured")?;
+                let mut guard = cache.lock().unwrap();
+                let stale = guard
+                    .as_ref()
+                    .map(|(_, fetched_at)| fetched_at.elapsed() > JWKS_CACHE_TTL)
+                    .unwrap_or(true);
+                if stale {
+                    *guard = Some((fetch_jwks(url)?, Instant::now()));
+                }
+                let key = guard
+                    .as_ref()
+                    .and_then(|(keys, _)| keys.get(kid))
+                    .cloned()
+                    .ok_or_else(|| format!("no JWKS key found for kid {kid}"))?;
+                Ok((key, algorithms.clone()))
+            }
+        }
+    }
+}
+
+fn fetch_jwks(url: &str) -> Result<HashMap<String, DecodingKey>, String> {
+    let jwks: jsonwebtoken::jwk::JwkSet = ureq::get(url)
+        .call()
+        .map_err(|e| format!("fetching JWKS from {url}: {e}"))?
+        .into_json()
+        .map_err(|e| format!("parsing JWKS from {url}: {e}"))?;
+
+    Ok(jwks
+        .keys
+        .iter()
+        .filter_map(|jwk| {
+            let kid = jwk.common.key_id.clone()?;
+            let key = DecodingKey::from_jwk(jwk).ok()?;
+            Some((kid, key))
+        })
+        .collect())
+}
+
+/// Returns `true` if `token` has the three dot-separated segments of a JWT.
+pub(crate) fn looks_like_jwt(token: &str) -> bool {
+    token.splitn(4, '.').count() == 3
+}
+
+/// Verify a JWT with an explicit key and build a `User` from its claims,
+/// without a DB round-trip. Returns `Ok(None)` for an expired token.
+///
+/// `algorithms` is the allow-list the *server* expects for this key — never
+/// the token's own `alg` header, which an attacker controls and which
+/// `jsonwebtoken` checks membership in, not identity with.
+pub(crate) fn validate_jwt(
+    token: &str,
+    key: &DecodingKey,
+    algorithms: &[Algorithm],
+) -> Result<Option<User>, String> {
+    let mut validation = Validation::new(
+        *algorithms.first().ok_or("no verification algorithm configured")?,
+    );
+    validation.algorithms = algorithms.to_vec();
+    let data = match decode::<Claims>(token, key, &validation) {
+        Ok(d) => d,
+        Err(e) => match e.kind() {
+            jsonwebtoken::errors::ErrorKind::ExpiredSignature => return Ok(None),
+            _ => return Err(format!("JWT verification failed: {e}")),
+        },
+    };
+    let claims = data.claims;
+    Ok(Some(User {
+        id: claims.sub,
+        email: claims.email,
+        name: claims.name,
+        plan: claims.plan,
+        role: claims.role,
+        email_verified: claims.email_verified,
+        created_at: claims
+            .iat
+            .and_then(|iat| chrono::DateTime::from_timestamp(iat, 0))
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_default(),
+        // A disabled account's JWTs are only rejected once Better Auth stops
+        // minting new ones and the existing token expires; this stateless
+        // path can't see the `banned` flag that `validate_session` checks.
+        enabled: true,
+    }))
+}
+
+/// Verify a JWT, resolving the verification key from a `JwtKeySource`
+/// (looking it up by the token's `kid` header for JWKS sources).
+pub(crate) fn validate_jwt_resolved(
+    token: &str,
+    source: &JwtKeySource,
+) -> Result<Option<User>, String> {
+    let kid = decode_header(token)
+        .map_err(|e| format!("malformed JWT header: {e}"))?
+        .kid;
+    let (key, algorithms) = source.resolve(kid.as_deref())?;
+    validate_jwt(token, &key, &algorithms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::json;
+
+    fn sign(claims: serde_json::Value, secret: &str) -> String {
+        encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_bytes())).unwrap()
+    }
+
+    #[test]
+    fn looks_like_jwt_requires_three_segments() {
+        assert!(looks_like_jwt("a.b.c"));
+        assert!(!looks_like_jwt("opaque-session-token"));
+        assert!(!looks_like_jwt("a.b"));
+        assert!(!looks_like_jwt("a.b.c.d"));
+    }
+
+    #[test]
+    fn validate_jwt_fills_in_claim_defaults() {
+        let secret = "test-secret";
+        let token = sign(
+            json!({
+                "sub": "user_1",
+                "email": "a@example.com",
+                "exp": 9_999_999_999i64,
+            }),
+            secret,
+        );
+        let key = DecodingKey::from_secret(secret.as_bytes());
+        let user = validate_jwt(&token, &key, &[Algorithm::HS256]).unwrap().unwrap();
+        assert_eq!(user.id, "user_1");
+        assert_eq!(user.plan, "free");
+        assert_eq!(user.role, "user");
+        assert!(!user.email_verified);
+        assert!(user.enabled);
+    }
+
+    #[test]
+    fn validate_jwt_returns_none_when_expired() {
+        let secret = "test-secret";
+        let token = sign(
+            json!({
+                "sub": "user_1",
+                "email": "a@example.com",
+                "exp": 1i64,
+            }),
+            secret,
+        );
+        let key = DecodingKey::from_secret(secret.as_bytes());
+        assert!(validate_jwt(&token, &key, &[Algorithm::HS256]).unwrap().is_none());
+    }
+
+    #[test]
+    fn validate_jwt_rejects_wrong_key() {
+        let token = sign(
+            json!({
+                "sub": "user_1",
+                "email": "a@example.com",
+                "exp": 9_999_999_999i64,
+            }),
+            "correct-secret",
+        );
+        let key = DecodingKey::from_secret(b"wrong-secret");
+        assert!(validate_jwt(&token, &key, &[Algorithm::HS256]).is_err());
+    }
+}
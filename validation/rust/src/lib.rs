@@ -1,7 +1,12 @@
 //! # corral-validate
 //!
 //! Minimal session validation for Corral/Better Auth.
-//! Reads the shared SQLite database directly via `rusqlite`.
+//! Reads the Better Auth database through a pluggable `SessionStore`
+//! (see the [`store`] module): SQLite by default, or Postgres/MySQL
+//! behind their feature flags, each pooled (via `r2d2` for SQLite and
+//! Postgres, via the `mysql` crate's own pool for MySQL) so a
+//! `validate_session` call checks out a pooled handle instead of opening a
+//! fresh connection.
 //!
 //! ## Auto-spawn auth server
 //! By default, set `with_auth_server(true)` on the builder to auto-spawn
@@ -12,8 +17,9 @@
 //! ## Basic usage
 //! ```no_run
 //! let v = corral_validate::CorralValidator::new("/data/auth.db").unwrap();
-//! if let Some(user) = v.validate_session("tok_abc").unwrap() {
-//!     println!("Hello, {}", user.email);
+//! match v.validate_session("tok_abc") {
+//!     Ok(user) => println!("Hello, {}", user.email),
+//!     Err(e) => println!("invalid session: {e}"),
 //! }
 //! ```
 //!
@@ -28,12 +34,70 @@
 //!
 //! ## Axum extractor
 //! The `CorralUser` type implements `FromRequestParts` when the
-//! `axum` feature is enabled. Add a `CorralValidator` to your app state:
+//! `axum` feature is enabled. Add a `CorralValidator` (or, with the
+//! `tokio` feature, an `AsyncCorralValidator`) to your app state:
 //! ```ignore
 //! async fn handler(user: CorralUser) -> String { user.0.email.clone() }
 //! ```
+//!
+//! ## Async API
+//! With the `tokio` feature enabled, `AsyncCorralValidator` offloads the
+//! blocking `rusqlite` calls to `spawn_blocking` so async handlers don't
+//! stall a worker thread on every request:
+//! ```ignore
+//! let v = corral_validate::AsyncCorralValidator::new(
+//!     corral_validate::CorralValidator::new("/data/auth.db").unwrap(),
+//! );
+//! let user = v.validate_session("tok_abc").await.unwrap();
+//! ```
+//!
+//! ## Stateless JWTs
+//! With the `jwt` feature enabled, configure a verification key (or a
+//! JWKS URL) on the builder and call `validate_token` to skip the DB for
+//! tokens signed by Better Auth's JWT plugin; opaque session tokens still
+//! fall back to `validate_session`:
+//! ```ignore
+//! let v = corral_validate::CorralValidator::builder("/data/auth.db")
+//!     .with_jwks_url("https://auth.example.com/api/auth/jwks", [jsonwebtoken::Algorithm::RS256])
+//!     .build()
+//!     .unwrap();
+//! let user = v.validate_token("eyJhbGciOi...").unwrap();
+//! ```
+//!
+//! ## Role/permission policy
+//! `require_plan` only ranks the four plan tiers; for finer-grained access
+//! control, register a [`Policy`] mapping roles to permissions (with
+//! optional inheritance) and check it with `User::has_permission`. Behind
+//! the `axum` feature, `RequirePermission<P>` is an extractor that composes
+//! with plan gating:
+//! ```ignore
+//! let policy = corral_validate::Policy::new()
+//!     .with_role("admin", ["users:read", "billing:write"]);
+//! assert!(user.has_permission(&policy, "users:read"));
+//! ```
+//!
+//! ## Errors
+//! `validate_session` and friends return [`CorralError`], which distinguishes
+//! "not found" from "expired" from "database unreachable". Behind the
+//! `axum` feature it implements `IntoResponse`, mapping each variant to a
+//! status code with a JSON `{status, message}` body.
+
+mod store;
+pub use store::{SessionStore, StoreError};
+
+mod error;
+pub use error::CorralError;
+
+#[cfg(feature = "jwt")]
+mod jwt;
+#[cfg(feature = "jwt")]
+use jwt::JwtKeySource;
+
+mod policy;
+pub use policy::Policy;
+#[cfg(feature = "axum")]
+pub use policy::{Permission, RequirePermission};
 
-use rusqlite::{Connection, params};
 use std::collections::HashMap;
 use std::io::{BufRead, BufReader};
 use std::process::{Child, Command, Stdio};
@@ -42,14 +106,40 @@ use std::time::{Duration, Instant};
 
 const COOKIE_NAME: &str = "better-auth.session_token";
 
+/// Wrap a JWT verification failure as a `CorralError::Database`, so it
+/// surfaces through the same variant as a store failure rather than being
+/// misrepresented as a SQLite error.
+#[cfg(feature = "jwt")]
+fn jwt_err(msg: String) -> CorralError {
+    CorralError::Database(msg.into())
+}
+
 fn plan_levels() -> HashMap<&'static str, u8> {
     [("free", 0), ("pro", 1), ("team", 2), ("enterprise", 3)]
         .into_iter()
         .collect()
 }
 
+/// Default refresh window for `validate_and_refresh_session` (1 day).
+const DEFAULT_REFRESH_WINDOW: Duration = Duration::from_secs(60 * 60 * 24);
+/// Default session lifetime written back on refresh (7 days).
+const DEFAULT_SESSION_LIFETIME: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+
+/// Parse `expiresAt` as either RFC3339 or "YYYY-MM-DD HH:MM:SS", both of
+/// which Better Auth is known to emit depending on the underlying DB driver.
+fn parse_expiry(expires_at: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::parse_from_rfc3339(expires_at)
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .ok()
+        .or_else(|| {
+            chrono::NaiveDateTime::parse_from_str(expires_at, "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .map(|dt| dt.and_utc())
+        })
+}
+
 /// An authenticated user.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct User {
     pub id: String,
     pub email: String,
@@ -58,12 +148,39 @@ pub struct User {
     pub role: String,
     pub email_verified: bool,
     pub created_at: String,
+    /// `false` for accounts disabled via `set_user_enabled`; `validate_session`
+    /// rejects these even with an otherwise-valid session.
+    pub enabled: bool,
+}
+
+/// Summary of one of a user's sessions, as returned by `list_sessions`.
+/// The token is truncated to a short prefix so it's safe to log or display.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SessionInfo {
+    pub token_prefix: String,
+    pub created_at: String,
+    pub expires_at: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+/// The new expiry written back to the session row by
+/// `validate_and_refresh_session`, when the session falls within the
+/// refresh window.
+#[derive(Debug, Clone, Copy)]
+pub struct NewExpiry {
+    pub expires_at: chrono::DateTime<chrono::Utc>,
 }
 
 /// Builder for configuring a `CorralValidator`.
 pub struct CorralValidatorBuilder {
     db_path: String,
     auth_server: bool,
+    pool_size: u32,
+    refresh_window: Duration,
+    session_lifetime: Duration,
+    #[cfg(feature = "jwt")]
+    jwt_keys: Option<JwtKeySource>,
 }
 
 impl CorralValidatorBuilder {
@@ -73,34 +190,94 @@ impl CorralValidatorBuilder {
         self
     }
 
+    /// Set the maximum size of the backend's connection pool (default 5).
+    pub fn with_pool_size(mut self, size: u32) -> Self {
+        self.pool_size = size;
+        self
+    }
+
+    /// Set how close to expiry a session must be for
+    /// `validate_and_refresh_session` to bump it (default 1 day).
+    pub fn with_refresh_window(mut self, window: Duration) -> Self {
+        self.refresh_window = window;
+        self
+    }
+
+    /// Set the lifetime written back to a session's `expiresAt` on refresh
+    /// (default 7 days).
+    pub fn with_session_lifetime(mut self, lifetime: Duration) -> Self {
+        self.session_lifetime = lifetime;
+        self
+    }
+
+    /// Verify JWTs signed with this fixed key, accepting only `algorithm`
+    /// (see also `with_jwks_url`). The algorithm is pinned here rather than
+    /// trusted from the token's own header, which an attacker controls.
+    #[cfg(feature = "jwt")]
+    pub fn with_jwt_key(mut self, key: jsonwebtoken::DecodingKey, algorithm: jsonwebtoken::Algorithm) -> Self {
+        self.jwt_keys = Some(JwtKeySource::Static(key, vec![algorithm]));
+        self
+    }
+
+    /// Verify JWTs using keys fetched (and cached) from a JWKS endpoint,
+    /// accepting only the given `algorithms`.
+    #[cfg(feature = "jwt")]
+    pub fn with_jwks_url(
+        mut self,
+        url: impl Into<String>,
+        algorithms: impl IntoIterator<Item = jsonwebtoken::Algorithm>,
+    ) -> Self {
+        self.jwt_keys = Some(JwtKeySource::Jwks {
+            url: url.into(),
+            algorithms: algorithms.into_iter().collect(),
+            cache: Mutex::new(None),
+        });
+        self
+    }
+
     /// Build the validator, optionally spawning the auth server.
-    pub fn build(self) -> rusqlite::Result<CorralValidator> {
-        let _ = Connection::open(&self.db_path)?;
+    ///
+    /// The backend is chosen by the scheme of `db_path`: `postgres://` or
+    /// `mysql://` select the matching feature-gated `SessionStore`, and
+    /// anything else is treated as a SQLite file path. Returns
+    /// `CorralError::AuthServerUnavailable` if `with_auth_server(true)` was
+    /// set but `server/auth.js` couldn't be found, Node isn't installed, or
+    /// the process couldn't be spawned (see `start_auth_server`).
+    pub fn build(self) -> Result<CorralValidator, CorralError> {
+        let store = store::store_for_url(&self.db_path, self.pool_size)
+            .map_err(CorralError::Database)?;
         let mut v = CorralValidator {
             db_path: self.db_path,
+            store,
             auth_child: Arc::new(Mutex::new(None)),
+            refresh_window: self.refresh_window,
+            session_lifetime: self.session_lifetime,
+            #[cfg(feature = "jwt")]
+            jwt_keys: self.jwt_keys,
         };
         if self.auth_server {
-            v.start_auth_server();
+            v.start_auth_server()?;
         }
         Ok(v)
     }
 }
 
-/// Session validator backed by a SQLite database.
+/// Session validator backed by a pluggable `SessionStore` (SQLite by
+/// default; see the [`store`] module for Postgres/MySQL).
 pub struct CorralValidator {
     db_path: String,
+    store: Box<dyn SessionStore>,
     auth_child: Arc<Mutex<Option<Child>>>,
+    refresh_window: Duration,
+    session_lifetime: Duration,
+    #[cfg(feature = "jwt")]
+    jwt_keys: Option<JwtKeySource>,
 }
 
 impl CorralValidator {
     /// Create a validator without auth server (backwards compatible).
-    pub fn new(db_path: &str) -> rusqlite::Result<Self> {
-        let _ = Connection::open(db_path)?;
-        Ok(Self {
-            db_path: db_path.to_string(),
-            auth_child: Arc::new(Mutex::new(None)),
-        })
+    pub fn new(db_path: &str) -> Result<Self, CorralError> {
+        Self::builder(db_path).build()
     }
 
     /// Create a builder for more configuration options.
@@ -108,25 +285,34 @@ impl CorralValidator {
         CorralValidatorBuilder {
             db_path: db_path.to_string(),
             auth_server: false,
+            pool_size: store::sqlite_default_pool_size(),
+            refresh_window: DEFAULT_REFRESH_WINDOW,
+            session_lifetime: DEFAULT_SESSION_LIFETIME,
+            #[cfg(feature = "jwt")]
+            jwt_keys: None,
         }
     }
 
-    /// Spawn the Node auth server as a managed child process.
-    pub fn start_auth_server(&mut self) {
+    /// Spawn the Node auth server as a managed child process. Returns
+    /// `CorralError::AuthServerUnavailable` if `server/auth.js` can't be
+    /// found, Node isn't installed, or the process fails to spawn; a health
+    /// check that hasn't passed within 5s is only logged, since the child is
+    /// already running and may simply still be starting up.
+    pub fn start_auth_server(&mut self) -> Result<(), CorralError> {
         let port = std::env::var("CORRAL_AUTH_PORT").unwrap_or_else(|_| "3456".into());
 
         let server_path = match self.find_auth_server() {
             Some(p) => p,
             None => {
                 eprintln!("[corral-auth] server/auth.js not found — auth operations won't work, session validation still works");
-                return;
+                return Err(CorralError::AuthServerUnavailable);
             }
         };
 
         // Check node is available
         if Command::new("node").arg("--version").output().is_err() {
             eprintln!("[corral-auth] Node.js not installed — skipping auth server spawn");
-            return;
+            return Err(CorralError::AuthServerUnavailable);
         }
 
         let child = Command::new("node")
@@ -140,7 +326,7 @@ impl CorralValidator {
             Ok(c) => c,
             Err(e) => {
                 eprintln!("[corral-auth] Failed to spawn auth server: {e}");
-                return;
+                return Err(CorralError::AuthServerUnavailable);
             }
         };
 
@@ -187,6 +373,7 @@ impl CorralValidator {
         } else {
             eprintln!("[corral-auth] Auth server health check failed after 5s — it may still be starting");
         }
+        Ok(())
     }
 
     fn find_auth_server(&self) -> Option<String> {
@@ -238,60 +425,128 @@ impl CorralValidator {
         *guard = None;
     }
 
-    fn conn(&self) -> rusqlite::Result<Connection> {
-        Connection::open(&self.db_path)
+    /// Validate a session token. Returns the user if the session is valid,
+    /// not expired, and the account hasn't been disabled.
+    pub fn validate_session(&self, token: &str) -> Result<User, CorralError> {
+        let (user_id, expires_at) = self
+            .store
+            .fetch_session(token)
+            .map_err(CorralError::Database)?
+            .ok_or(CorralError::NotFound)?;
+
+        let expires_at = parse_expiry(&expires_at).ok_or(CorralError::MalformedExpiry)?;
+        if expires_at < chrono::Utc::now() {
+            return Err(CorralError::Expired);
+        }
+        self.get_user_by_id(&user_id)
     }
 
-    /// Validate a session token. Returns the user if valid and not expired.
-    pub fn validate_session(&self, token: &str) -> rusqlite::Result<Option<User>> {
-        let conn = self.conn()?;
-        let mut stmt = conn.prepare(
-            r#"SELECT "userId", "expiresAt" FROM "session" WHERE "token" = ?1"#
-        )?;
-        let result = stmt.query_row(params![token], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-        });
-        let (user_id, expires_at) = match result {
-            Ok(v) => v,
-            Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
-            Err(e) => return Err(e),
+    /// Validate a session token, and if it's within the configured refresh
+    /// window of expiring, write a new expiry back to the `session` row
+    /// (sliding-window refresh). The second tuple element is `Some` only
+    /// when the expiry was actually bumped, so the caller knows to re-set
+    /// the session cookie (see `session_cookie_header`).
+    pub fn validate_and_refresh_session(
+        &self,
+        token: &str,
+    ) -> Result<(User, Option<NewExpiry>), CorralError> {
+        let (user_id, expires_at) = self
+            .store
+            .fetch_session(token)
+            .map_err(CorralError::Database)?
+            .ok_or(CorralError::NotFound)?;
+        let expires_at = parse_expiry(&expires_at).ok_or(CorralError::MalformedExpiry)?;
+        if expires_at < chrono::Utc::now() {
+            return Err(CorralError::Expired);
+        }
+        let user = self.get_user_by_id(&user_id)?;
+
+        let refresh_window = chrono::Duration::from_std(self.refresh_window).unwrap_or_else(|_| chrono::Duration::zero());
+        let new_expiry = if chrono::Utc::now() >= expires_at - refresh_window {
+            let lifetime = chrono::Duration::from_std(self.session_lifetime).unwrap_or_else(|_| chrono::Duration::zero());
+            let new_expires_at = chrono::Utc::now() + lifetime;
+            let updated = self
+                .store
+                .refresh_session(token, &new_expires_at.to_rfc3339())
+                .map_err(CorralError::Database)?;
+            updated.then_some(NewExpiry { expires_at: new_expires_at })
+        } else {
+            None
         };
 
-        // Parse expiry — accept RFC3339 or "YYYY-MM-DD HH:MM:SS"
-        let expired = chrono::DateTime::parse_from_rfc3339(&expires_at)
-            .map(|dt| dt < chrono::Utc::now())
-            .unwrap_or_else(|_| {
-                chrono::NaiveDateTime::parse_from_str(&expires_at, "%Y-%m-%d %H:%M:%S")
-                    .map(|dt| dt.and_utc() < chrono::Utc::now())
-                    .unwrap_or(true)
-            });
-        if expired {
-            return Ok(None);
-        }
-        self.get_user_by_id(&conn, &user_id)
-    }
-
-    fn get_user_by_id(&self, conn: &Connection, user_id: &str) -> rusqlite::Result<Option<User>> {
-        let mut stmt = conn.prepare(
-            r#"SELECT "id","email","name","plan","role","emailVerified","createdAt"
-               FROM "user" WHERE "id" = ?1"#
-        )?;
-        let result = stmt.query_row(params![user_id], |row| {
-            Ok(User {
-                id: row.get(0)?,
-                email: row.get(1)?,
-                name: row.get(2)?,
-                plan: row.get::<_, Option<String>>(3)?.unwrap_or_else(|| "free".into()),
-                role: row.get::<_, Option<String>>(4)?.unwrap_or_else(|| "user".into()),
-                email_verified: row.get::<_, bool>(5).unwrap_or(false),
-                created_at: row.get(6)?,
-            })
-        });
-        match result {
-            Ok(u) => Ok(Some(u)),
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(e),
+        Ok((user, new_expiry))
+    }
+
+    /// Look up a user, rejecting disabled accounts so every session path
+    /// (`validate_session`, `validate_and_refresh_session`) honors bans.
+    fn get_user_by_id(&self, user_id: &str) -> Result<User, CorralError> {
+        self.store
+            .fetch_user(user_id)
+            .map_err(CorralError::Database)?
+            .filter(|u| u.enabled)
+            .ok_or(CorralError::NotFound)
+    }
+
+    /// List a user's active sessions (e.g. for a "your devices" account page).
+    /// Sessions whose `expiresAt` has passed are left out; one whose
+    /// `expiresAt` can't be parsed is kept.
+    pub fn list_sessions(&self, user_id: &str) -> Result<Vec<SessionInfo>, CorralError> {
+        let now = chrono::Utc::now();
+        let sessions = self.store.list_sessions(user_id).map_err(CorralError::Database)?;
+        Ok(sessions
+            .into_iter()
+            .filter(|s| parse_expiry(&s.expires_at).map_or(true, |expires_at| expires_at >= now))
+            .collect())
+    }
+
+    /// Revoke a single session by token ("sign out this device").
+    pub fn revoke_session(&self, token: &str) -> Result<bool, CorralError> {
+        self.store.delete_session(token).map_err(CorralError::Database)
+    }
+
+    /// Revoke all of a user's sessions ("sign out everywhere").
+    pub fn revoke_all_sessions(&self, user_id: &str) -> Result<u64, CorralError> {
+        self.store.delete_all_sessions(user_id).map_err(CorralError::Database)
+    }
+
+    /// Enable or disable a user's account. A disabled account fails
+    /// `validate_session` even with an otherwise-valid, unexpired session.
+    pub fn set_user_enabled(&self, user_id: &str, enabled: bool) -> Result<bool, CorralError> {
+        self.store.set_user_enabled(user_id, enabled).map_err(CorralError::Database)
+    }
+
+    /// Verify a JWT with an explicit key, accepting only `algorithm`, and
+    /// build its `User` without a DB round-trip, falling back to
+    /// `validate_session` when `token` isn't a JWT (detected by its
+    /// two-dot structure). `algorithm` must come from server configuration,
+    /// never from the token itself.
+    #[cfg(feature = "jwt")]
+    pub fn validate_jwt(
+        &self,
+        token: &str,
+        key: &jsonwebtoken::DecodingKey,
+        algorithm: jsonwebtoken::Algorithm,
+    ) -> Result<User, CorralError> {
+        if !jwt::looks_like_jwt(token) {
+            return self.validate_session(token);
         }
+        jwt::validate_jwt(token, key, &[algorithm])
+            .map_err(jwt_err)?
+            .ok_or(CorralError::Expired)
+    }
+
+    /// Validate a token using the builder-configured JWT key or JWKS URL,
+    /// falling back to `validate_session` for opaque tokens or when no JWT
+    /// key is configured. This is the zero-DB-hit fast path for JWTs.
+    #[cfg(feature = "jwt")]
+    pub fn validate_token(&self, token: &str) -> Result<User, CorralError> {
+        let source = match &self.jwt_keys {
+            Some(source) if jwt::looks_like_jwt(token) => source,
+            _ => return self.validate_session(token),
+        };
+        jwt::validate_jwt_resolved(token, source)
+            .map_err(jwt_err)?
+            .ok_or(CorralError::Expired)
     }
 
     /// Check if a user's plan meets the minimum required plan.
@@ -308,6 +563,44 @@ impl Drop for CorralValidator {
     }
 }
 
+/// Async wrapper around `CorralValidator` for use inside async handlers.
+///
+/// The underlying `rusqlite` work is still blocking, so every call is
+/// offloaded to a `tokio::task::spawn_blocking` worker over the validator's
+/// connection pool rather than running on the async executor's own thread.
+/// The sync `CorralValidator` API is unchanged for non-async callers.
+#[cfg(feature = "tokio")]
+#[derive(Clone)]
+pub struct AsyncCorralValidator {
+    inner: Arc<CorralValidator>,
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncCorralValidator {
+    /// Wrap a `CorralValidator` for async use.
+    pub fn new(inner: CorralValidator) -> Self {
+        Self { inner: Arc::new(inner) }
+    }
+
+    /// Async equivalent of `CorralValidator::validate_session`.
+    pub async fn validate_session(&self, token: &str) -> Result<User, CorralError> {
+        let inner = self.inner.clone();
+        let token = token.to_string();
+        tokio::task::spawn_blocking(move || inner.validate_session(&token))
+            .await
+            .expect("validate_session blocking task panicked")
+    }
+
+    /// Async equivalent of the private `CorralValidator::get_user_by_id`.
+    pub async fn get_user_by_id(&self, user_id: &str) -> Result<User, CorralError> {
+        let inner = self.inner.clone();
+        let user_id = user_id.to_string();
+        tokio::task::spawn_blocking(move || inner.get_user_by_id(&user_id))
+            .await
+            .expect("get_user_by_id blocking task panicked")
+    }
+}
+
 /// Extract session token from HTTP headers (cookie or Authorization: Bearer).
 pub fn extract_token(headers: &[(String, String)]) -> Option<String> {
     // Check cookie header
@@ -329,18 +622,71 @@ pub fn extract_token(headers: &[(String, String)]) -> Option<String> {
     None
 }
 
+/// Build the `Set-Cookie` header value for `better-auth.session_token`
+/// after `validate_and_refresh_session` bumps a session's expiry. Set
+/// `secure` to `false` only for local-dev setups served over plain HTTP;
+/// anything reachable over the network should keep it `true` so the
+/// refreshed session token can't be replayed after being sniffed in transit.
+pub fn session_cookie_header(token: &str, expiry: &NewExpiry, secure: bool) -> String {
+    let secure = if secure { "; Secure" } else { "" };
+    format!(
+        "{COOKIE_NAME}={token}; Path=/; HttpOnly; SameSite=Lax{secure}; Expires={}",
+        expiry.expires_at.format("%a, %d %b %Y %H:%M:%S GMT")
+    )
+}
+
 // --- Axum extractor (behind axum feature) ---
 #[cfg(feature = "axum")]
 pub struct CorralUser(pub User);
 
 #[cfg(feature = "axum")]
+fn token_from_parts(parts: &axum::http::request::Parts) -> Option<String> {
+    parts.headers.get("cookie")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').find_map(|p| {
+            p.trim().strip_prefix("better-auth.session_token=").map(String::from)
+        }))
+        .or_else(|| {
+            parts.headers.get("authorization")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.strip_prefix("Bearer "))
+                .map(String::from)
+        })
+}
+
+// With the `tokio` feature, the extractor awaits `AsyncCorralValidator` so
+// the blocking rusqlite work never runs directly on the Tokio worker.
+#[cfg(all(feature = "axum", feature = "tokio"))]
+#[async_trait::async_trait]
+impl<S> axum::extract::FromRequestParts<S> for CorralUser
+where
+    S: Send + Sync,
+    AsyncCorralValidator: axum::extract::FromRef<S>,
+{
+    type Rejection = CorralError;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        use axum::extract::FromRef;
+
+        let validator = AsyncCorralValidator::from_ref(state);
+        let token = token_from_parts(parts).ok_or(CorralError::NotFound)?;
+
+        Ok(CorralUser(validator.validate_session(&token).await?))
+    }
+}
+
+// Without the `tokio` feature, fall back to the blocking sync API.
+#[cfg(all(feature = "axum", not(feature = "tokio")))]
 #[async_trait::async_trait]
 impl<S> axum::extract::FromRequestParts<S> for CorralUser
 where
     S: Send + Sync,
     CorralValidator: axum::extract::FromRef<S>,
 {
-    type Rejection = axum::http::StatusCode;
+    type Rejection = CorralError;
 
     async fn from_request_parts(
         parts: &mut axum::http::request::Parts,
@@ -349,22 +695,172 @@ where
         use axum::extract::FromRef;
 
         let validator = CorralValidator::from_ref(state);
-        let token = parts.headers.get("cookie")
-            .and_then(|v| v.to_str().ok())
-            .and_then(|v| v.split(';').find_map(|p| {
-                p.trim().strip_prefix("better-auth.session_token=").map(String::from)
-            }))
-            .or_else(|| {
-                parts.headers.get("authorization")
-                    .and_then(|v| v.to_str().ok())
-                    .and_then(|v| v.strip_prefix("Bearer "))
-                    .map(String::from)
+        let token = token_from_parts(parts).ok_or(CorralError::NotFound)?;
+
+        Ok(CorralUser(validator.validate_session(&token)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db_path(name: &str) -> String {
+        let mut path = std::env::temp_dir();
+        path.push(format!("corral-validate-lib-test-{name}-{}.db", std::process::id()));
+        path.to_string_lossy().into_owned()
+    }
+
+    fn setup_db(path: &str) {
+        let conn = rusqlite::Connection::open(path).unwrap();
+        conn.execute_batch(
+            r#"
+            CREATE TABLE "user" (
+                "id" TEXT PRIMARY KEY,
+                "email" TEXT NOT NULL,
+                "name" TEXT,
+                "plan" TEXT,
+                "role" TEXT,
+                "emailVerified" INTEGER NOT NULL,
+                "createdAt" TEXT NOT NULL,
+                "banned" INTEGER
+            );
+            CREATE TABLE "session" (
+                "token" TEXT PRIMARY KEY,
+                "userId" TEXT NOT NULL,
+                "expiresAt" TEXT NOT NULL,
+                "createdAt" TEXT NOT NULL,
+                "ipAddress" TEXT,
+                "userAgent" TEXT
+            );
+            INSERT INTO "user" ("id", "email", "name", "plan", "role", "emailVerified", "createdAt", "banned")
+                VALUES ('user_1', 'a@example.com', 'Alice', 'pro', 'admin', 1, '2024-01-01T00:00:00Z', 0);
+            INSERT INTO "session" ("token", "userId", "expiresAt", "createdAt", "ipAddress", "userAgent")
+                VALUES ('tok_abc', 'user_1', '2099-01-01T00:00:00Z', '2024-01-01T00:00:00Z', '127.0.0.1', 'curl');
+            "#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn pool_size_allows_concurrent_validation() {
+        let path = temp_db_path("pool");
+        setup_db(&path);
+        let validator = Arc::new(CorralValidator::builder(&path).with_pool_size(2).build().unwrap());
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let validator = validator.clone();
+                std::thread::spawn(move || validator.validate_session("tok_abc").unwrap())
             })
-            .ok_or(axum::http::StatusCode::UNAUTHORIZED)?;
+            .collect();
+        for handle in handles {
+            let user = handle.join().unwrap();
+            assert_eq!(user.id, "user_1");
+        }
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_validator_validates_session_and_fetches_user() {
+        let path = temp_db_path("async");
+        setup_db(&path);
+        let validator = AsyncCorralValidator::new(CorralValidator::builder(&path).build().unwrap());
 
-        validator.validate_session(&token)
-            .map_err(|_| axum::http::StatusCode::INTERNAL_SERVER_ERROR)?
-            .map(CorralUser)
-            .ok_or(axum::http::StatusCode::UNAUTHORIZED)
+        let user = validator.validate_session("tok_abc").await.unwrap();
+        assert_eq!(user.id, "user_1");
+
+        let by_id = validator.get_user_by_id("user_1").await.unwrap();
+        assert_eq!(by_id.email, "a@example.com");
+
+        assert!(matches!(
+            validator.validate_session("no-such-token").await,
+            Err(CorralError::NotFound)
+        ));
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn session_management_wrappers_round_trip() {
+        let path = temp_db_path("sessions");
+        setup_db(&path);
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute(
+            r#"INSERT INTO "session" ("token", "userId", "expiresAt", "createdAt", "ipAddress", "userAgent")
+               VALUES ('tok_def', 'user_1', '2099-01-01T00:00:00Z', '2024-01-01T00:00:00Z', '10.0.0.1', 'curl')"#,
+            [],
+        )
+        .unwrap();
+        drop(conn);
+        let validator = CorralValidator::builder(&path).build().unwrap();
+
+        let sessions = validator.list_sessions("user_1").unwrap();
+        assert_eq!(sessions.len(), 2);
+
+        assert!(validator.revoke_session("tok_abc").unwrap());
+        assert!(!validator.revoke_session("tok_abc").unwrap());
+        assert_eq!(validator.list_sessions("user_1").unwrap().len(), 1);
+
+        assert_eq!(validator.revoke_all_sessions("user_1").unwrap(), 1);
+        assert!(validator.list_sessions("user_1").unwrap().is_empty());
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn set_user_enabled_blocks_and_restores_session_validation() {
+        let path = temp_db_path("disable");
+        setup_db(&path);
+        let validator = CorralValidator::builder(&path).build().unwrap();
+
+        assert!(validator.validate_session("tok_abc").is_ok());
+
+        assert!(validator.set_user_enabled("user_1", false).unwrap());
+        assert!(matches!(validator.validate_session("tok_abc"), Err(CorralError::NotFound)));
+
+        assert!(validator.set_user_enabled("user_1", true).unwrap());
+        assert!(validator.validate_session("tok_abc").is_ok());
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn parse_expiry_accepts_rfc3339() {
+        let parsed = parse_expiry("2024-01-01T00:00:00Z").unwrap();
+        assert_eq!(parsed.to_rfc3339(), "2024-01-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn parse_expiry_accepts_sql_datetime() {
+        let parsed = parse_expiry("2024-01-01 00:00:00").unwrap();
+        assert_eq!(parsed.to_rfc3339(), "2024-01-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn parse_expiry_rejects_garbage() {
+        assert!(parse_expiry("not a date").is_none());
+    }
+
+    #[test]
+    fn require_plan_ranks_tiers() {
+        let mut user = User {
+            id: "u1".into(),
+            email: "a@example.com".into(),
+            name: None,
+            plan: "pro".into(),
+            role: "user".into(),
+            email_verified: true,
+            created_at: "2024-01-01T00:00:00Z".into(),
+            enabled: true,
+        };
+        assert!(CorralValidator::require_plan(&user, "free"));
+        assert!(CorralValidator::require_plan(&user, "pro"));
+        assert!(!CorralValidator::require_plan(&user, "team"));
+        user.plan = "unknown-tier".into();
+        assert!(CorralValidator::require_plan(&user, "free"));
+        assert!(!CorralValidator::require_plan(&user, "pro"));
     }
 }
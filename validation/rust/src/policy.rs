@@ -0,0 +1,218 @@
+//! Role/permission policy layer, complementing the plan-tier gating in
+//! `CorralValidator::require_plan`. A `Policy` maps roles to permission
+//! sets (with optional role inheritance); `User::has_permission` checks a
+//! user's role against it. Behind the `axum` feature, `RequirePermission<P>`
+//! is an extractor wrapper that rejects with `403` when the permission is
+//! missing, and composes with plan gating: it hands the resolved `User` to
+//! the handler, which can pass it to `CorralValidator::require_plan`.
+
+use crate::User;
+use std::collections::{HashMap, HashSet};
+
+/// Maps roles to permission sets, with optional role inheritance.
+///
+/// ```
+/// use corral_validate::Policy;
+///
+/// let policy = Policy::new()
+///     .with_role("support", ["users:read"])
+///     .with_role("admin", ["users:read", "billing:write"])
+///     .with_inherits("owner", ["admin"]);
+///
+/// assert!(policy.role_has_permission("support", "users:read"));
+/// assert!(!policy.role_has_permission("support", "billing:write"));
+/// assert!(policy.role_has_permission("owner", "billing:write"));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Policy {
+    permissions: HashMap<String, HashSet<String>>,
+    inherits: HashMap<String, Vec<String>>,
+}
+
+impl Policy {
+    /// An empty policy — no role grants any permission until configured.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a role with the permissions it directly grants.
+    pub fn with_role<R, P, S>(mut self, role: R, permissions: P) -> Self
+    where
+        R: Into<String>,
+        P: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.permissions
+            .insert(role.into(), permissions.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Make `role` inherit every permission granted to `parents`
+    /// (transitively).
+    pub fn with_inherits<R, P, S>(mut self, role: R, parents: P) -> Self
+    where
+        R: Into<String>,
+        P: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.inherits
+            .entry(role.into())
+            .or_default()
+            .extend(parents.into_iter().map(Into::into));
+        self
+    }
+
+    /// Check whether `role` grants `permission`, directly or via inheritance.
+    pub fn role_has_permission(&self, role: &str, permission: &str) -> bool {
+        self.role_has_permission_inner(role, permission, &mut HashSet::new())
+    }
+
+    fn role_has_permission_inner(
+        &self,
+        role: &str,
+        permission: &str,
+        visited: &mut HashSet<String>,
+    ) -> bool {
+        if !visited.insert(role.to_string()) {
+            return false; // inheritance cycle guard
+        }
+        if self
+            .permissions
+            .get(role)
+            .is_some_and(|granted| granted.contains(permission))
+        {
+            return true;
+        }
+        self.inherits
+            .get(role)
+            .into_iter()
+            .flatten()
+            .any(|parent| self.role_has_permission_inner(parent, permission, visited))
+    }
+}
+
+impl User {
+    /// Check this user's role against a `Policy`.
+    pub fn has_permission(&self, policy: &Policy, permission: &str) -> bool {
+        policy.role_has_permission(&self.role, permission)
+    }
+}
+
+/// A permission required by the `RequirePermission<P>` extractor. Implement
+/// this for a small marker type per permission:
+/// ```ignore
+/// struct UsersRead;
+/// impl corral_validate::Permission for UsersRead {
+///     const NAME: &'static str = "users:read";
+/// }
+/// ```
+#[cfg(feature = "axum")]
+pub trait Permission {
+    const NAME: &'static str;
+}
+
+/// Axum extractor that resolves a `CorralUser` and rejects with `403` unless
+/// their role grants `P::NAME` under the app's `Policy`. Add a `Policy` to
+/// your app state alongside the `CorralValidator`:
+/// ```ignore
+/// async fn handler(RequirePermission(user, _): RequirePermission<UsersRead>) -> String {
+///     user.email
+/// }
+/// ```
+#[cfg(feature = "axum")]
+pub struct RequirePermission<P>(pub User, pub std::marker::PhantomData<P>);
+
+#[cfg(feature = "axum")]
+#[async_trait::async_trait]
+impl<S, P> axum::extract::FromRequestParts<S> for RequirePermission<P>
+where
+    S: Send + Sync,
+    Policy: axum::extract::FromRef<S>,
+    P: Permission + Send + Sync,
+    crate::CorralUser: axum::extract::FromRequestParts<S, Rejection = crate::CorralError>,
+{
+    type Rejection = axum::response::Response;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        use axum::extract::FromRef;
+        use axum::response::IntoResponse;
+
+        let crate::CorralUser(user) =
+            <crate::CorralUser as axum::extract::FromRequestParts<S>>::from_request_parts(parts, state)
+                .await
+                .map_err(IntoResponse::into_response)?;
+        let policy = Policy::from_ref(state);
+        if user.has_permission(&policy, P::NAME) {
+            Ok(RequirePermission(user, std::marker::PhantomData))
+        } else {
+            let body = axum::Json(PermissionDenied { status: 403, message: format!("missing permission `{}`", P::NAME) });
+            Err((axum::http::StatusCode::FORBIDDEN, body).into_response())
+        }
+    }
+}
+
+#[cfg(feature = "axum")]
+#[derive(serde::Serialize)]
+struct PermissionDenied {
+    status: u16,
+    message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn direct_and_transitive_inheritance() {
+        let policy = Policy::new()
+            .with_role("support", ["users:read"])
+            .with_role("admin", ["billing:write"])
+            .with_inherits("admin", ["support"])
+            .with_inherits("owner", ["admin"]);
+
+        assert!(policy.role_has_permission("support", "users:read"));
+        assert!(!policy.role_has_permission("support", "billing:write"));
+        assert!(policy.role_has_permission("admin", "users:read"));
+        assert!(policy.role_has_permission("owner", "users:read"));
+        assert!(policy.role_has_permission("owner", "billing:write"));
+        assert!(!policy.role_has_permission("owner", "users:write"));
+    }
+
+    #[test]
+    fn unknown_role_has_no_permissions() {
+        let policy = Policy::new().with_role("admin", ["users:read"]);
+        assert!(!policy.role_has_permission("nobody", "users:read"));
+    }
+
+    #[test]
+    fn inheritance_cycle_does_not_loop_forever() {
+        let policy = Policy::new()
+            .with_role("a", ["perm:a"])
+            .with_inherits("a", ["b"])
+            .with_inherits("b", ["a"]);
+
+        assert!(policy.role_has_permission("a", "perm:a"));
+        assert!(policy.role_has_permission("b", "perm:a"));
+        assert!(!policy.role_has_permission("a", "perm:nonexistent"));
+    }
+
+    #[test]
+    fn user_has_permission_checks_its_own_role() {
+        let policy = Policy::new().with_role("admin", ["users:read"]);
+        let user = User {
+            id: "u1".into(),
+            email: "a@example.com".into(),
+            name: None,
+            plan: "free".into(),
+            role: "admin".into(),
+            email_verified: true,
+            created_at: "2024-01-01T00:00:00Z".into(),
+            enabled: true,
+        };
+        assert!(user.has_permission(&policy, "users:read"));
+        assert!(!user.has_permission(&policy, "billing:write"));
+    }
+}
@@ -0,0 +1,101 @@
+//! Pluggable session/user storage for `CorralValidator`.
+//!
+//! `SessionStore` abstracts the Better Auth database lookups so the
+//! validator isn't hardcoded to SQLite. The `sqlite` backend is always
+//! available and backs the crate's default behavior; `postgres` and
+//! `mysql` are available behind their respective feature flags and are
+//! selected by the scheme of the connection string passed to
+//! `CorralValidator::builder` (`postgres://`, `mysql://`, or a bare
+//! filesystem path for SQLite).
+
+use crate::{SessionInfo, User};
+
+mod sqlite;
+pub(crate) use sqlite::SqliteStore;
+
+/// Shorten a session token to a prefix that's safe to log or display.
+pub(crate) fn token_prefix(token: &str) -> String {
+    let prefix: String = token.chars().take(8).collect();
+    format!("{prefix}…")
+}
+
+/// Default pool size used when the builder doesn't override it.
+pub(crate) fn sqlite_default_pool_size() -> u32 {
+    sqlite::DEFAULT_POOL_SIZE
+}
+
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "postgres")]
+pub(crate) use postgres::PostgresStore;
+
+#[cfg(feature = "mysql")]
+mod mysql;
+#[cfg(feature = "mysql")]
+pub(crate) use mysql::MysqlStore;
+
+/// Error type shared by all `SessionStore` implementations.
+pub type StoreError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A session/user store backing `CorralValidator`.
+///
+/// Implementations return `Ok(None)` for "not found" and propagate real
+/// failures (connection errors, malformed rows, etc.) as `Err`.
+pub trait SessionStore: Send + Sync {
+    /// Look up a session by token, returning `(user_id, expires_at)`.
+    fn fetch_session(&self, token: &str) -> Result<Option<(String, String)>, StoreError>;
+
+    /// Look up a user by id.
+    fn fetch_user(&self, id: &str) -> Result<Option<User>, StoreError>;
+
+    /// Write a new expiry to a session row. Returns whether a row was updated.
+    fn refresh_session(&self, token: &str, new_expires_at: &str) -> Result<bool, StoreError>;
+
+    /// List all of a user's sessions, including expired ones not yet deleted.
+    fn list_sessions(&self, user_id: &str) -> Result<Vec<SessionInfo>, StoreError>;
+
+    /// Delete a single session by token. Returns whether a row was deleted.
+    fn delete_session(&self, token: &str) -> Result<bool, StoreError>;
+
+    /// Delete all of a user's sessions. Returns the number of rows deleted.
+    fn delete_all_sessions(&self, user_id: &str) -> Result<u64, StoreError>;
+
+    /// Enable or disable a user's account. Returns whether a row was updated.
+    fn set_user_enabled(&self, user_id: &str, enabled: bool) -> Result<bool, StoreError>;
+}
+
+/// Build the `SessionStore` implied by a connection string's scheme.
+pub(crate) fn store_for_url(db_path: &str, pool_size: u32) -> Result<Box<dyn SessionStore>, StoreError> {
+    if db_path.starts_with("postgres://") || db_path.starts_with("postgresql://") {
+        return build_postgres(db_path, pool_size);
+    }
+    if db_path.starts_with("mysql://") {
+        return build_mysql(db_path, pool_size);
+    }
+    SqliteStore::connect(db_path, pool_size).map(|s| Box::new(s) as Box<dyn SessionStore>)
+}
+
+#[cfg(feature = "postgres")]
+fn build_postgres(url: &str, pool_size: u32) -> Result<Box<dyn SessionStore>, StoreError> {
+    PostgresStore::connect(url, pool_size).map(|s| Box::new(s) as Box<dyn SessionStore>)
+}
+
+#[cfg(not(feature = "postgres"))]
+fn build_postgres(_url: &str, _pool_size: u32) -> Result<Box<dyn SessionStore>, StoreError> {
+    Err(unsupported_scheme("postgres", "postgres"))
+}
+
+#[cfg(feature = "mysql")]
+fn build_mysql(url: &str, pool_size: u32) -> Result<Box<dyn SessionStore>, StoreError> {
+    MysqlStore::connect(url, pool_size).map(|s| Box::new(s) as Box<dyn SessionStore>)
+}
+
+#[cfg(not(feature = "mysql"))]
+fn build_mysql(_url: &str, _pool_size: u32) -> Result<Box<dyn SessionStore>, StoreError> {
+    Err(unsupported_scheme("mysql", "mysql"))
+}
+
+#[allow(dead_code)]
+fn unsupported_scheme(scheme: &str, feature: &str) -> StoreError {
+    format!("`{scheme}://` connection strings require building corral-validate with the `{feature}` feature").into()
+}
@@ -0,0 +1,138 @@
+//! MySQL-backed `SessionStore`, available behind the `mysql` feature.
+
+use super::{token_prefix, SessionStore, StoreError};
+use crate::{SessionInfo, User};
+use mysql::prelude::Queryable;
+use mysql::params;
+
+pub(crate) struct MysqlStore {
+    pool: mysql::Pool,
+    /// Whether `user` has the `banned` column added alongside
+    /// `set_user_enabled`. Older databases predate it, so `fetch_user`
+    /// falls back to `enabled: true` instead of erroring every lookup.
+    has_banned_column: bool,
+}
+
+impl MysqlStore {
+    pub(crate) fn connect(url: &str, pool_size: u32) -> Result<Self, StoreError> {
+        let opts = mysql::Opts::from_url(url)?;
+        let constraints = mysql::PoolConstraints::new(1, pool_size as usize)
+            .ok_or("pool size must be at least 1")?;
+        let pool_opts = mysql::PoolOpts::default().with_constraints(constraints);
+        let builder = mysql::OptsBuilder::from_opts(opts).pool_opts(pool_opts);
+        let pool = mysql::Pool::new(builder)?;
+
+        let has_banned_column: bool = pool.get_conn()?.exec_first(
+            "SELECT COUNT(*) > 0 FROM information_schema.columns \
+             WHERE table_name = 'user' AND column_name = 'banned'",
+            (),
+        )?.unwrap_or(false);
+
+        Ok(Self { pool, has_banned_column })
+    }
+}
+
+impl SessionStore for MysqlStore {
+    fn fetch_session(&self, token: &str) -> Result<Option<(String, String)>, StoreError> {
+        let mut conn = self.pool.get_conn()?;
+        let row = conn.exec_first(
+            "SELECT userId, expiresAt FROM session WHERE token = :token",
+            params! { "token" => token },
+        )?;
+        Ok(row)
+    }
+
+    fn fetch_user(&self, id: &str) -> Result<Option<User>, StoreError> {
+        let mut conn = self.pool.get_conn()?;
+        if self.has_banned_column {
+            #[allow(clippy::type_complexity)]
+            let row: Option<(String, String, Option<String>, Option<String>, Option<String>, bool, String, Option<bool>)> =
+                conn.exec_first(
+                    "SELECT id, email, name, plan, role, emailVerified, createdAt, banned FROM user WHERE id = :id",
+                    params! { "id" => id },
+                )?;
+            Ok(row.map(|(id, email, name, plan, role, email_verified, created_at, banned)| User {
+                id,
+                email,
+                name,
+                plan: plan.unwrap_or_else(|| "free".into()),
+                role: role.unwrap_or_else(|| "user".into()),
+                email_verified,
+                created_at,
+                enabled: !banned.unwrap_or(false),
+            }))
+        } else {
+            #[allow(clippy::type_complexity)]
+            let row: Option<(String, String, Option<String>, Option<String>, Option<String>, bool, String)> =
+                conn.exec_first(
+                    "SELECT id, email, name, plan, role, emailVerified, createdAt FROM user WHERE id = :id",
+                    params! { "id" => id },
+                )?;
+            Ok(row.map(|(id, email, name, plan, role, email_verified, created_at)| User {
+                id,
+                email,
+                name,
+                plan: plan.unwrap_or_else(|| "free".into()),
+                role: role.unwrap_or_else(|| "user".into()),
+                email_verified,
+                created_at,
+                enabled: true,
+            }))
+        }
+    }
+
+    fn refresh_session(&self, token: &str, new_expires_at: &str) -> Result<bool, StoreError> {
+        let mut conn = self.pool.get_conn()?;
+        conn.exec_drop(
+            "UPDATE session SET expiresAt = :expires_at WHERE token = :token",
+            params! { "expires_at" => new_expires_at, "token" => token },
+        )?;
+        Ok(conn.affected_rows() > 0)
+    }
+
+    fn list_sessions(&self, user_id: &str) -> Result<Vec<SessionInfo>, StoreError> {
+        let mut conn = self.pool.get_conn()?;
+        #[allow(clippy::type_complexity)]
+        let rows: Vec<(String, String, String, Option<String>, Option<String>)> = conn.exec(
+            "SELECT token, createdAt, expiresAt, ipAddress, userAgent FROM session WHERE userId = :user_id",
+            params! { "user_id" => user_id },
+        )?;
+        Ok(rows
+            .into_iter()
+            .map(|(token, created_at, expires_at, ip_address, user_agent)| SessionInfo {
+                token_prefix: token_prefix(&token),
+                created_at,
+                expires_at,
+                ip_address,
+                user_agent,
+            })
+            .collect())
+    }
+
+    fn delete_session(&self, token: &str) -> Result<bool, StoreError> {
+        let mut conn = self.pool.get_conn()?;
+        conn.exec_drop(
+            "DELETE FROM session WHERE token = :token",
+            params! { "token" => token },
+        )?;
+        Ok(conn.affected_rows() > 0)
+    }
+
+    fn delete_all_sessions(&self, user_id: &str) -> Result<u64, StoreError> {
+        let mut conn = self.pool.get_conn()?;
+        conn.exec_drop(
+            "DELETE FROM session WHERE userId = :user_id",
+            params! { "user_id" => user_id },
+        )?;
+        Ok(conn.affected_rows())
+    }
+
+    fn set_user_enabled(&self, user_id: &str, enabled: bool) -> Result<bool, StoreError> {
+        let mut conn = self.pool.get_conn()?;
+        conn.exec_drop(
+            "UPDATE user SET banned = :banned WHERE id = :user_id",
+            params! { "banned" => !enabled, "user_id" => user_id },
+        )?;
+        Ok(conn.affected_rows() > 0)
+    }
+}
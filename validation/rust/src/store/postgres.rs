@@ -0,0 +1,117 @@
+//! Postgres-backed `SessionStore`, available behind the `postgres` feature.
+
+use super::{token_prefix, SessionStore, StoreError};
+use crate::{SessionInfo, User};
+use r2d2_postgres::{postgres::NoTls, PostgresConnectionManager};
+
+type Pool = r2d2::Pool<PostgresConnectionManager<NoTls>>;
+
+pub(crate) struct PostgresStore {
+    pool: Pool,
+    /// Whether `"user"` has the `"banned"` column added alongside
+    /// `set_user_enabled`. Older databases predate it, so `fetch_user`
+    /// falls back to `enabled: true` instead of erroring every lookup.
+    has_banned_column: bool,
+}
+
+impl PostgresStore {
+    pub(crate) fn connect(url: &str, pool_size: u32) -> Result<Self, StoreError> {
+        let manager = PostgresConnectionManager::new(url.parse()?, NoTls);
+        let pool = r2d2::Pool::builder().max_size(pool_size).build(manager)?;
+        let has_banned_column = pool.get()?.query_one(
+            r#"SELECT EXISTS (SELECT 1 FROM information_schema.columns
+                              WHERE table_name = 'user' AND column_name = 'banned')"#,
+            &[],
+        )?.get::<_, bool>(0);
+        Ok(Self { pool, has_banned_column })
+    }
+}
+
+impl SessionStore for PostgresStore {
+    fn fetch_session(&self, token: &str) -> Result<Option<(String, String)>, StoreError> {
+        let mut conn = self.pool.get()?;
+        let row = conn.query_opt(
+            r#"SELECT "userId", "expiresAt"::text FROM "session" WHERE "token" = $1"#,
+            &[&token],
+        )?;
+        Ok(row.map(|r| (r.get(0), r.get(1))))
+    }
+
+    fn fetch_user(&self, id: &str) -> Result<Option<User>, StoreError> {
+        let mut conn = self.pool.get()?;
+        let query = if self.has_banned_column {
+            r#"SELECT "id","email","name","plan","role","emailVerified","createdAt"::text,"banned"
+               FROM "user" WHERE "id" = $1"#
+        } else {
+            r#"SELECT "id","email","name","plan","role","emailVerified","createdAt"::text
+               FROM "user" WHERE "id" = $1"#
+        };
+        let row = conn.query_opt(query, &[&id])?;
+        Ok(row.map(|r| User {
+            id: r.get(0),
+            email: r.get(1),
+            name: r.get(2),
+            plan: r.get::<_, Option<String>>(3).unwrap_or_else(|| "free".into()),
+            role: r.get::<_, Option<String>>(4).unwrap_or_else(|| "user".into()),
+            email_verified: r.get(5),
+            created_at: r.get(6),
+            enabled: if self.has_banned_column {
+                !r.get::<_, Option<bool>>(7).unwrap_or(false)
+            } else {
+                true
+            },
+        }))
+    }
+
+    fn refresh_session(&self, token: &str, new_expires_at: &str) -> Result<bool, StoreError> {
+        let mut conn = self.pool.get()?;
+        let updated = conn.execute(
+            r#"UPDATE "session" SET "expiresAt" = $1::timestamptz WHERE "token" = $2"#,
+            &[&new_expires_at, &token],
+        )?;
+        Ok(updated > 0)
+    }
+
+    fn list_sessions(&self, user_id: &str) -> Result<Vec<SessionInfo>, StoreError> {
+        let mut conn = self.pool.get()?;
+        let rows = conn.query(
+            r#"SELECT "token","createdAt"::text,"expiresAt"::text,"ipAddress","userAgent"
+               FROM "session" WHERE "userId" = $1"#,
+            &[&user_id],
+        )?;
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                let token: String = r.get(0);
+                SessionInfo {
+                    token_prefix: token_prefix(&token),
+                    created_at: r.get(1),
+                    expires_at: r.get(2),
+                    ip_address: r.get(3),
+                    user_agent: r.get(4),
+                }
+            })
+            .collect())
+    }
+
+    fn delete_session(&self, token: &str) -> Result<bool, StoreError> {
+        let mut conn = self.pool.get()?;
+        let deleted = conn.execute(r#"DELETE FROM "session" WHERE "token" = $1"#, &[&token])?;
+        Ok(deleted > 0)
+    }
+
+    fn delete_all_sessions(&self, user_id: &str) -> Result<u64, StoreError> {
+        let mut conn = self.pool.get()?;
+        let deleted = conn.execute(r#"DELETE FROM "session" WHERE "userId" = $1"#, &[&user_id])?;
+        Ok(deleted)
+    }
+
+    fn set_user_enabled(&self, user_id: &str, enabled: bool) -> Result<bool, StoreError> {
+        let mut conn = self.pool.get()?;
+        let updated = conn.execute(
+            r#"UPDATE "user" SET "banned" = $1 WHERE "id" = $2"#,
+            &[&!enabled, &user_id],
+        )?;
+        Ok(updated > 0)
+    }
+}
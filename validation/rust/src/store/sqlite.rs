@@ -0,0 +1,289 @@
+//! SQLite-backed `SessionStore`, the crate's default.
+
+use super::{token_prefix, SessionStore, StoreError};
+use crate::{SessionInfo, User};
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::params;
+
+/// Default number of pooled connections when the builder doesn't override it.
+pub(crate) const DEFAULT_POOL_SIZE: u32 = 5;
+
+type Pool = r2d2::Pool<SqliteConnectionManager>;
+
+pub(crate) struct SqliteStore {
+    /// Read-only pool used for the hot session/user lookup path.
+    pool: Pool,
+    /// Single-connection pool (SQLite only allows one writer at a time)
+    /// for the occasional write, e.g. session refresh/revocation.
+    write_pool: Pool,
+    /// Whether `"user"` has the `"banned"` column added alongside
+    /// `set_user_enabled`. Older databases predate it, so `fetch_user`
+    /// falls back to `enabled: true` instead of erroring every lookup.
+    has_banned_column: bool,
+}
+
+impl SqliteStore {
+    pub(crate) fn connect(db_path: &str, pool_size: u32) -> Result<Self, StoreError> {
+        let manager = SqliteConnectionManager::file(db_path).with_init(|conn| {
+            conn.execute_batch(
+                "PRAGMA query_only = ON; PRAGMA busy_timeout = 5000; PRAGMA journal_mode = WAL;",
+            )
+        });
+        let pool = r2d2::Pool::builder().max_size(pool_size).build(manager)?;
+
+        let write_manager = SqliteConnectionManager::file(db_path).with_init(|conn| {
+            conn.execute_batch("PRAGMA busy_timeout = 5000; PRAGMA journal_mode = WAL;")
+        });
+        let write_pool = r2d2::Pool::builder().max_size(1).build(write_manager)?;
+
+        let has_banned_column = pool.get()?.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('user') WHERE name = 'banned'",
+            [],
+            |row| row.get::<_, i64>(0),
+        )? > 0;
+
+        Ok(Self { pool, write_pool, has_banned_column })
+    }
+}
+
+impl SessionStore for SqliteStore {
+    fn fetch_session(&self, token: &str) -> Result<Option<(String, String)>, StoreError> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            r#"SELECT "userId", "expiresAt" FROM "session" WHERE "token" = ?1"#,
+        )?;
+        let result = stmt.query_row(params![token], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        });
+        match result {
+            Ok(v) => Ok(Some(v)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(Box::new(e)),
+        }
+    }
+
+    fn fetch_user(&self, id: &str) -> Result<Option<User>, StoreError> {
+        let conn = self.pool.get()?;
+        let query = if self.has_banned_column {
+            r#"SELECT "id","email","name","plan","role","emailVerified","createdAt","banned"
+               FROM "user" WHERE "id" = ?1"#
+        } else {
+            r#"SELECT "id","email","name","plan","role","emailVerified","createdAt"
+               FROM "user" WHERE "id" = ?1"#
+        };
+        let mut stmt = conn.prepare(query)?;
+        let has_banned_column = self.has_banned_column;
+        let result = stmt.query_row(params![id], |row| {
+            Ok(User {
+                id: row.get(0)?,
+                email: row.get(1)?,
+                name: row.get(2)?,
+                plan: row.get::<_, Option<String>>(3)?.unwrap_or_else(|| "free".into()),
+                role: row.get::<_, Option<String>>(4)?.unwrap_or_else(|| "user".into()),
+                email_verified: row.get::<_, bool>(5).unwrap_or(false),
+                created_at: row.get(6)?,
+                enabled: if has_banned_column {
+                    !row.get::<_, Option<bool>>(7)?.unwrap_or(false)
+                } else {
+                    true
+                },
+            })
+        });
+        match result {
+            Ok(u) => Ok(Some(u)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(Box::new(e)),
+        }
+    }
+
+    fn refresh_session(&self, token: &str, new_expires_at: &str) -> Result<bool, StoreError> {
+        let conn = self.write_pool.get()?;
+        let updated = conn.execute(
+            r#"UPDATE "session" SET "expiresAt" = ?1 WHERE "token" = ?2"#,
+            params![new_expires_at, token],
+        )?;
+        Ok(updated > 0)
+    }
+
+    fn list_sessions(&self, user_id: &str) -> Result<Vec<SessionInfo>, StoreError> {
+        let conn = self.pool.get()?;
+        let mut stmt = conn.prepare(
+            r#"SELECT "token","createdAt","expiresAt","ipAddress","userAgent"
+               FROM "session" WHERE "userId" = ?1"#,
+        )?;
+        let rows = stmt.query_map(params![user_id], |row| {
+            let token: String = row.get(0)?;
+            Ok(SessionInfo {
+                token_prefix: token_prefix(&token),
+                created_at: row.get(1)?,
+                expires_at: row.get(2)?,
+                ip_address: row.get(3)?,
+                user_agent: row.get(4)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(|e| Box::new(e) as StoreError)
+    }
+
+    fn delete_session(&self, token: &str) -> Result<bool, StoreError> {
+        let conn = self.write_pool.get()?;
+        let deleted = conn.execute(r#"DELETE FROM "session" WHERE "token" = ?1"#, params![token])?;
+        Ok(deleted > 0)
+    }
+
+    fn delete_all_sessions(&self, user_id: &str) -> Result<u64, StoreError> {
+        let conn = self.write_pool.get()?;
+        let deleted = conn.execute(
+            r#"DELETE FROM "session" WHERE "userId" = ?1"#,
+            params![user_id],
+        )?;
+        Ok(deleted as u64)
+    }
+
+    fn set_user_enabled(&self, user_id: &str, enabled: bool) -> Result<bool, StoreError> {
+        let conn = self.write_pool.get()?;
+        let updated = conn.execute(
+            r#"UPDATE "user" SET "banned" = ?1 WHERE "id" = ?2"#,
+            params![!enabled, user_id],
+        )?;
+        Ok(updated > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db_path(name: &str) -> String {
+        let mut path = std::env::temp_dir();
+        path.push(format!("corral-validate-test-{name}-{}.db", std::process::id()));
+        path.to_string_lossy().into_owned()
+    }
+
+    fn setup(path: &str) {
+        let conn = rusqlite::Connection::open(path).unwrap();
+        conn.execute_batch(
+            r#"
+            CREATE TABLE "user" (
+                "id" TEXT PRIMARY KEY,
+                "email" TEXT NOT NULL,
+                "name" TEXT,
+                "plan" TEXT,
+                "role" TEXT,
+                "emailVerified" INTEGER NOT NULL,
+                "createdAt" TEXT NOT NULL,
+                "banned" INTEGER
+            );
+            CREATE TABLE "session" (
+                "token" TEXT PRIMARY KEY,
+                "userId" TEXT NOT NULL,
+                "expiresAt" TEXT NOT NULL,
+                "createdAt" TEXT NOT NULL,
+                "ipAddress" TEXT,
+                "userAgent" TEXT
+            );
+            INSERT INTO "user" ("id", "email", "name", "plan", "role", "emailVerified", "createdAt", "banned")
+                VALUES ('user_1', 'a@example.com', 'Alice', 'pro', 'admin', 1, '2024-01-01T00:00:00Z', 0);
+            INSERT INTO "user" ("id", "email", "name", "plan", "role", "emailVerified", "createdAt", "banned")
+                VALUES ('user_2', 'b@example.com', NULL, 'free', 'user', 0, '2024-01-01T00:00:00Z', 1);
+            INSERT INTO "session" ("token", "userId", "expiresAt", "createdAt", "ipAddress", "userAgent")
+                VALUES ('tok_abc', 'user_1', '2099-01-01T00:00:00Z', '2024-01-01T00:00:00Z', '127.0.0.1', 'curl');
+            "#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn fetch_session_and_user_round_trip() {
+        let path = temp_db_path("fetch");
+        setup(&path);
+        let store = SqliteStore::connect(&path, 1).unwrap();
+
+        let (user_id, expires_at) = store.fetch_session("tok_abc").unwrap().unwrap();
+        assert_eq!(user_id, "user_1");
+        assert_eq!(expires_at, "2099-01-01T00:00:00Z");
+        assert!(store.fetch_session("no-such-token").unwrap().is_none());
+
+        let user = store.fetch_user("user_1").unwrap().unwrap();
+        assert_eq!(user.email, "a@example.com");
+        assert_eq!(user.plan, "pro");
+        assert!(user.enabled);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn fetch_user_respects_banned_column() {
+        let path = temp_db_path("banned");
+        setup(&path);
+        let store = SqliteStore::connect(&path, 1).unwrap();
+
+        let banned_user = store.fetch_user("user_2").unwrap().unwrap();
+        assert!(!banned_user.enabled);
+        assert_eq!(banned_user.plan, "free");
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn refresh_and_revoke_session() {
+        let path = temp_db_path("refresh");
+        setup(&path);
+        let store = SqliteStore::connect(&path, 1).unwrap();
+
+        assert!(store.refresh_session("tok_abc", "2099-06-01T00:00:00Z").unwrap());
+        let (_, expires_at) = store.fetch_session("tok_abc").unwrap().unwrap();
+        assert_eq!(expires_at, "2099-06-01T00:00:00Z");
+
+        let sessions = store.list_sessions("user_1").unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].ip_address.as_deref(), Some("127.0.0.1"));
+
+        assert!(store.delete_session("tok_abc").unwrap());
+        assert!(store.fetch_session("tok_abc").unwrap().is_none());
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn set_user_enabled_toggles_banned_column() {
+        let path = temp_db_path("enable");
+        setup(&path);
+        let store = SqliteStore::connect(&path, 1).unwrap();
+
+        assert!(store.set_user_enabled("user_1", false).unwrap());
+        assert!(!store.fetch_user("user_1").unwrap().unwrap().enabled);
+        assert!(store.set_user_enabled("user_1", true).unwrap());
+        assert!(store.fetch_user("user_1").unwrap().unwrap().enabled);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn fetch_user_defaults_enabled_without_banned_column() {
+        let path = temp_db_path("no-banned-column");
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute_batch(
+            r#"
+            CREATE TABLE "user" (
+                "id" TEXT PRIMARY KEY,
+                "email" TEXT NOT NULL,
+                "name" TEXT,
+                "plan" TEXT,
+                "role" TEXT,
+                "emailVerified" INTEGER NOT NULL,
+                "createdAt" TEXT NOT NULL
+            );
+            INSERT INTO "user" ("id", "email", "name", "plan", "role", "emailVerified", "createdAt")
+                VALUES ('user_1', 'a@example.com', 'Alice', 'pro', 'admin', 1, '2024-01-01T00:00:00Z');
+            "#,
+        )
+        .unwrap();
+        drop(conn);
+
+        let store = SqliteStore::connect(&path, 1).unwrap();
+        let user = store.fetch_user("user_1").unwrap().unwrap();
+        assert!(user.enabled);
+
+        std::fs::remove_file(&path).ok();
+    }
+}